@@ -4,14 +4,18 @@ use self::explicit_le::ExplicitVRLittleEndianDecoder;
 use self::implicit_le::{ImplicitVRLittleEndianDecoder, StandardImplicitVRLittleEndianDecoder};
 use byteordered::Endianness;
 use dicom_core::header::{DataElementHeader, SequenceItemHeader};
-use dicom_core::Tag;
-use snafu::{Backtrace, Snafu};
+use dicom_core::value::PrimitiveValue;
+use dicom_core::{Tag, VR};
+use snafu::{Backtrace, ResultExt, Snafu};
 use std::io::{self, Read};
 
 pub mod basic;
 pub mod explicit_be;
 pub mod explicit_le;
 pub mod implicit_le;
+pub mod options;
+
+pub use self::options::{DecodeOptions, UnknownVrBehavior};
 
 /// Module-level error type:
 /// for errors which may occur while decoding DICOM data.
@@ -57,10 +61,143 @@ pub enum Error {
     BadSequenceHeader {
         source: dicom_core::header::SequenceItemHeaderError,
     },
+    #[snafu(display("Value length {} exceeds the configured maximum of {}", length, max))]
+    ValueLengthExceedsLimit { length: u32, max: u32 },
+    #[snafu(display("Value length {} is odd, which is not allowed by the current decode options", length))]
+    OddLength { length: u32 },
+    #[snafu(display(
+        "Value length {} is not a multiple of the {}-byte element width",
+        length,
+        width
+    ))]
+    MisalignedLength { length: u32, width: u32 },
+    #[snafu(display("Failed to read the element's value"))]
+    ReadValue {
+        backtrace: Backtrace,
+        source: io::Error,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// The host's native byte order, used by the `decode_*_into` bulk readers
+/// to decide whether a byte-swap pass is needed after the raw copy.
+#[cfg(target_endian = "little")]
+const NATIVE_ENDIANNESS: Endianness = Endianness::Little;
+#[cfg(target_endian = "big")]
+const NATIVE_ENDIANNESS: Endianness = Endianness::Big;
+
+/** A reader abstraction over a DICOM data source which tracks the absolute
+ * byte offset consumed so far and allows peeking at the next data element
+ * tag without consuming it.
+ *
+ * This removes the need for each [`Decode`] implementation to manually
+ * count and return the number of bytes it read: the number of bytes
+ * consumed by an operation can always be derived from the difference in
+ * [`position`](DicomRead::position) before and after it. It also gives the
+ * dataset and sequence reading layers a way to detect item and sequence
+ * delimiters (`FFFE,E00D` / `FFFE,E0DD`) by peeking ahead before committing
+ * to a read.
+ */
+pub trait DicomRead: Read {
+    /// The absolute byte offset into the source consumed so far.
+    fn position(&self) -> u64;
+
+    /// Peek at the next data element tag without consuming it from the
+    /// source: a subsequent read sees the same bytes again.
+    fn peek_tag(&mut self, endianness: Endianness) -> io::Result<Tag>;
+}
+
+/** Wraps a plain [`Read`] source to provide the position tracking and tag
+ * lookahead required by [`DicomRead`]. Any [`Read`] source can be used with
+ * the decoders in this module by first wrapping it in a `TrackedReader`.
+ */
+#[derive(Debug)]
+pub struct TrackedReader<S> {
+    source: S,
+    position: u64,
+    /// Bytes already read from `source` but not yet consumed by the caller
+    /// (via [`peek_tag`](DicomRead::peek_tag)), along with how many of the
+    /// leading bytes in the buffer are still valid and unread.
+    lookahead: Option<[u8; 4]>,
+    lookahead_len: u8,
+}
+
+impl<S> TrackedReader<S> {
+    /// Wrap a source so its consumed byte offset can be tracked.
+    pub fn new(source: S) -> Self {
+        TrackedReader {
+            source,
+            position: 0,
+            lookahead: None,
+            lookahead_len: 0,
+        }
+    }
+}
+
+impl<S: Read> Read for TrackedReader<S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut written = 0;
+        if let Some(lookahead) = self.lookahead {
+            let len = self.lookahead_len as usize;
+            let to_copy = len.min(buf.len());
+            buf[..to_copy].copy_from_slice(&lookahead[..to_copy]);
+            written += to_copy;
+            if to_copy < len {
+                // only the genuinely unread bytes carry over; do not pad
+                // the remainder with synthetic zeros
+                let mut remainder = [0u8; 4];
+                remainder[..len - to_copy].copy_from_slice(&lookahead[to_copy..len]);
+                self.lookahead = Some(remainder);
+                self.lookahead_len = (len - to_copy) as u8;
+                self.position += written as u64;
+                return Ok(written);
+            }
+            self.lookahead = None;
+            self.lookahead_len = 0;
+        }
+        let n = self.source.read(&mut buf[written..])?;
+        // bytes handed out from the lookahead count as consumed only now
+        self.position += (written + n) as u64;
+        Ok(written + n)
+    }
+}
+
+impl<S: Read> DicomRead for TrackedReader<S> {
+    fn position(&self) -> u64 {
+        self.position
+    }
+
+    fn peek_tag(&mut self, endianness: Endianness) -> io::Result<Tag> {
+        let have = self.lookahead_len as usize;
+        let buf = if have == 4 {
+            self.lookahead.unwrap()
+        } else {
+            // drain whatever of the lookahead is still valid before pulling
+            // more bytes from the source, instead of discarding it
+            let mut buf = [0u8; 4];
+            if have > 0 {
+                buf[..have].copy_from_slice(&self.lookahead.unwrap()[..have]);
+            }
+            self.source.read_exact(&mut buf[have..])?;
+            self.lookahead = Some(buf);
+            self.lookahead_len = 4;
+            buf
+        };
+        let (g, e) = match endianness {
+            Endianness::Little => (
+                u16::from_le_bytes([buf[0], buf[1]]),
+                u16::from_le_bytes([buf[2], buf[3]]),
+            ),
+            Endianness::Big => (
+                u16::from_be_bytes([buf[0], buf[1]]),
+                u16::from_be_bytes([buf[2], buf[3]]),
+            ),
+        };
+        Ok(Tag(g, e))
+    }
+}
+
 /** Obtain the default data element decoder.
  * According to the standard, data elements are encoded in Implicit
  * VR Little Endian by default.
@@ -96,14 +233,22 @@ pub trait BasicDecode {
 
     /// Decode a sequence of unsigned shorts value from the given source
     /// into the given destination.
+    ///
+    /// This reads the whole destination span in a single `read_exact` call
+    /// and, only when the decoder's endianness differs from the host's,
+    /// follows it with an in-place byte swap. On a matching-endian host
+    /// (the common case for Explicit/Implicit VR Little Endian) this is a
+    /// single copy with no per-element overhead.
     fn decode_us_into<S>(&self, mut source: S, dst: &mut [u16]) -> std::io::Result<()>
     where
         S: Read,
     {
-        for v in dst.iter_mut() {
-            *v = self.decode_us(&mut source)?;
+        source.read_exact(bytemuck::cast_slice_mut(dst))?;
+        if self.endianness() != NATIVE_ENDIANNESS {
+            for v in dst.iter_mut() {
+                *v = v.swap_bytes();
+            }
         }
-
         Ok(())
     }
 
@@ -113,15 +258,18 @@ pub trait BasicDecode {
         S: Read;
 
     /// Decode a sequence of unsigned long values from the given source
-    /// into the given destination.
+    /// into the given destination. See [`decode_us_into`](BasicDecode::decode_us_into)
+    /// for the bulk read/swap strategy used.
     fn decode_ul_into<S>(&self, mut source: S, dst: &mut [u32]) -> std::io::Result<()>
     where
         S: Read,
     {
-        for v in dst.iter_mut() {
-            *v = self.decode_ul(&mut source)?;
+        source.read_exact(bytemuck::cast_slice_mut(dst))?;
+        if self.endianness() != NATIVE_ENDIANNESS {
+            for v in dst.iter_mut() {
+                *v = v.swap_bytes();
+            }
         }
-
         Ok(())
     }
 
@@ -131,15 +279,18 @@ pub trait BasicDecode {
         S: Read;
 
     /// Decode a sequence of unsigned very long values from the given source
-    /// into the given destination.
+    /// into the given destination. See [`decode_us_into`](BasicDecode::decode_us_into)
+    /// for the bulk read/swap strategy used.
     fn decode_uv_into<S>(&self, mut source: S, dst: &mut [u64]) -> std::io::Result<()>
     where
         S: Read,
     {
-        for v in dst.iter_mut() {
-            *v = self.decode_uv(&mut source)?;
+        source.read_exact(bytemuck::cast_slice_mut(dst))?;
+        if self.endianness() != NATIVE_ENDIANNESS {
+            for v in dst.iter_mut() {
+                *v = v.swap_bytes();
+            }
         }
-
         Ok(())
     }
 
@@ -149,15 +300,18 @@ pub trait BasicDecode {
         S: Read;
 
     /// Decode a sequence of signed short values from the given source
-    /// into the given destination.
+    /// into the given destination. See [`decode_us_into`](BasicDecode::decode_us_into)
+    /// for the bulk read/swap strategy used.
     fn decode_ss_into<S>(&self, mut source: S, dst: &mut [i16]) -> std::io::Result<()>
     where
         S: Read,
     {
-        for v in dst.iter_mut() {
-            *v = self.decode_ss(&mut source)?;
+        source.read_exact(bytemuck::cast_slice_mut(dst))?;
+        if self.endianness() != NATIVE_ENDIANNESS {
+            for v in dst.iter_mut() {
+                *v = v.swap_bytes();
+            }
         }
-
         Ok(())
     }
 
@@ -167,15 +321,18 @@ pub trait BasicDecode {
         S: Read;
 
     /// Decode a sequence of signed long values from the given source
-    /// into the given destination.
+    /// into the given destination. See [`decode_us_into`](BasicDecode::decode_us_into)
+    /// for the bulk read/swap strategy used.
     fn decode_sl_into<S>(&self, mut source: S, dst: &mut [i32]) -> std::io::Result<()>
     where
         S: Read,
     {
-        for v in dst.iter_mut() {
-            *v = self.decode_sl(&mut source)?;
+        source.read_exact(bytemuck::cast_slice_mut(dst))?;
+        if self.endianness() != NATIVE_ENDIANNESS {
+            for v in dst.iter_mut() {
+                *v = v.swap_bytes();
+            }
         }
-
         Ok(())
     }
 
@@ -185,15 +342,18 @@ pub trait BasicDecode {
         S: Read;
 
     /// Decode a sequence of signed very long values from the given source
-    /// into the given destination.
+    /// into the given destination. See [`decode_us_into`](BasicDecode::decode_us_into)
+    /// for the bulk read/swap strategy used.
     fn decode_sv_into<S>(&self, mut source: S, dst: &mut [i64]) -> std::io::Result<()>
     where
         S: Read,
     {
-        for v in dst.iter_mut() {
-            *v = self.decode_sv(&mut source)?;
+        source.read_exact(bytemuck::cast_slice_mut(dst))?;
+        if self.endianness() != NATIVE_ENDIANNESS {
+            for v in dst.iter_mut() {
+                *v = v.swap_bytes();
+            }
         }
-
         Ok(())
     }
 
@@ -203,15 +363,18 @@ pub trait BasicDecode {
         S: Read;
 
     /// Decode a sequence of single precision float values from the given source
-    /// into the given destination.
+    /// into the given destination. See [`decode_us_into`](BasicDecode::decode_us_into)
+    /// for the bulk read/swap strategy used.
     fn decode_fl_into<S>(&self, mut source: S, dst: &mut [f32]) -> std::io::Result<()>
     where
         S: Read,
     {
-        for v in dst.iter_mut() {
-            *v = self.decode_fl(&mut source)?;
+        source.read_exact(bytemuck::cast_slice_mut(dst))?;
+        if self.endianness() != NATIVE_ENDIANNESS {
+            for v in dst.iter_mut() {
+                *v = f32::from_bits(v.to_bits().swap_bytes());
+            }
         }
-
         Ok(())
     }
 
@@ -221,15 +384,18 @@ pub trait BasicDecode {
         S: Read;
 
     /// Decode a sequence of double precision float values from the given source
-    /// into the given destination.
+    /// into the given destination. See [`decode_us_into`](BasicDecode::decode_us_into)
+    /// for the bulk read/swap strategy used.
     fn decode_fd_into<S>(&self, mut source: S, dst: &mut [f64]) -> std::io::Result<()>
     where
         S: Read,
     {
-        for v in dst.iter_mut() {
-            *v = self.decode_fd(&mut source)?;
+        source.read_exact(bytemuck::cast_slice_mut(dst))?;
+        if self.endianness() != NATIVE_ENDIANNESS {
+            for v in dst.iter_mut() {
+                *v = f64::from_bits(v.to_bits().swap_bytes());
+            }
         }
-
         Ok(())
     }
 
@@ -500,12 +666,44 @@ where
     }
 }
 
+/** Run `f` over `source` and return its result together with the number of
+ * bytes it consumed, derived from the difference in
+ * [`DicomRead::position`] before and after the call.
+ *
+ * [`Decode`] implementations should use this instead of summing up the
+ * width of each field they read by hand: it can't drift out of sync with
+ * what was actually read off the wire.
+ */
+pub(crate) fn decode_counting<S, T>(
+    source: &mut S,
+    f: impl FnOnce(&mut S) -> Result<T>,
+) -> Result<(T, usize)>
+where
+    S: ?Sized + DicomRead,
+{
+    let start = source.position();
+    let value = f(source)?;
+    let read = (source.position() - start) as usize;
+    Ok((value, read))
+}
+
+/// Divide a value length by a fixed-width element's size, rejecting lengths
+/// that aren't an exact multiple instead of silently truncating via integer
+/// division (which would under-read the declared value and desync the
+/// stream for every subsequent [`Decode::decode_header`] call).
+fn element_count(length: u32, width: u32) -> Result<usize> {
+    if length % width != 0 {
+        return MisalignedLengthSnafu { length, width }.fail();
+    }
+    Ok((length / width) as usize)
+}
+
 /** Type trait for reading and decoding DICOM data elements.
  *
  * The specific behaviour of decoding, even when abstracted from the original source,
  * may depend on the transfer syntax.
  */
-pub trait Decode {
+pub trait Decode: BasicDecode {
     /** Fetch and decode the next data element header from the given source.
      * This method returns only the header of the element. At the end of this operation, the source
      * will be pointing at the element's value data, which should be read or skipped as necessary.
@@ -517,7 +715,7 @@ pub trait Decode {
      */
     fn decode_header<S>(&self, source: &mut S) -> Result<(DataElementHeader, usize)>
     where
-        S: ?Sized + Read;
+        S: ?Sized + DicomRead;
 
     /** Fetch and decode the next sequence item head from the given source. It is a separate method
      * because value representation is always implicit when reading item headers and delimiters.
@@ -526,12 +724,136 @@ pub trait Decode {
      */
     fn decode_item_header<S>(&self, source: &mut S) -> Result<SequenceItemHeader>
     where
-        S: ?Sized + Read;
+        S: ?Sized + DicomRead;
 
     /// Decode a DICOM attribute tag from the given source.
     fn decode_tag<S>(&self, source: &mut S) -> Result<Tag>
     where
-        S: ?Sized + Read;
+        S: ?Sized + DicomRead;
+
+    /** Read `header.length()` bytes from `source` and decode them into
+     * `dst` as the primitive value type appropriate for the header's VR,
+     * dispatching to the matching [`BasicDecode`] bulk method.
+     *
+     * This gives callers a single entry point for reading a primitive
+     * element's value without having to inspect the VR and pick the right
+     * `decode_*`/`decode_*_into` method themselves. Common string, date and
+     * time VRs are split on the backslash delimiter and read as a typed
+     * string value; the remaining (mostly binary) VRs are read as raw
+     * bytes, matching how `UN` is handled elsewhere.
+     *
+     * `header.length()` is checked against `options`'
+     * [`max_element_length`](DecodeOptions::max_element_length) before any
+     * allocation is made, so a corrupt or crafted header can't drive an
+     * unbounded allocation.
+     */
+    fn decode_element_value<S>(
+        &self,
+        header: &DataElementHeader,
+        source: &mut S,
+        options: &DecodeOptions,
+    ) -> Result<PrimitiveValue>
+    where
+        S: ?Sized + DicomRead,
+    {
+        let length = header.length().0;
+        let max = options.max_element_length();
+        if length > max {
+            return ValueLengthExceedsLimitSnafu { length, max }.fail();
+        }
+        if length % 2 != 0 && !options.allow_odd_length() {
+            return OddLengthSnafu { length }.fail();
+        }
+        let len = length as usize;
+        let value = match header.vr() {
+            VR::US => {
+                let mut v = vec![0u16; element_count(length, 2)?];
+                self.decode_us_into(&mut *source, &mut v)
+                    .context(ReadValueSnafu)?;
+                PrimitiveValue::U16(v.into())
+            }
+            VR::UL => {
+                let mut v = vec![0u32; element_count(length, 4)?];
+                self.decode_ul_into(&mut *source, &mut v)
+                    .context(ReadValueSnafu)?;
+                PrimitiveValue::U32(v.into())
+            }
+            VR::UV => {
+                let mut v = vec![0u64; element_count(length, 8)?];
+                self.decode_uv_into(&mut *source, &mut v)
+                    .context(ReadValueSnafu)?;
+                PrimitiveValue::U64(v.into())
+            }
+            VR::SS => {
+                let mut v = vec![0i16; element_count(length, 2)?];
+                self.decode_ss_into(&mut *source, &mut v)
+                    .context(ReadValueSnafu)?;
+                PrimitiveValue::I16(v.into())
+            }
+            VR::SL => {
+                let mut v = vec![0i32; element_count(length, 4)?];
+                self.decode_sl_into(&mut *source, &mut v)
+                    .context(ReadValueSnafu)?;
+                PrimitiveValue::I32(v.into())
+            }
+            VR::SV => {
+                let mut v = vec![0i64; element_count(length, 8)?];
+                self.decode_sv_into(&mut *source, &mut v)
+                    .context(ReadValueSnafu)?;
+                PrimitiveValue::I64(v.into())
+            }
+            VR::FL => {
+                let mut v = vec![0f32; element_count(length, 4)?];
+                self.decode_fl_into(&mut *source, &mut v)
+                    .context(ReadValueSnafu)?;
+                PrimitiveValue::F32(v.into())
+            }
+            VR::FD => {
+                let mut v = vec![0f64; element_count(length, 8)?];
+                self.decode_fd_into(&mut *source, &mut v)
+                    .context(ReadValueSnafu)?;
+                PrimitiveValue::F64(v.into())
+            }
+            VR::AT => {
+                let n = element_count(length, 4)?;
+                let mut tags = Vec::with_capacity(n);
+                for _ in 0..n {
+                    tags.push(self.decode_tag(&mut *source)?);
+                }
+                PrimitiveValue::Tags(tags.into())
+            }
+            VR::AE
+            | VR::AS
+            | VR::CS
+            | VR::DA
+            | VR::DS
+            | VR::DT
+            | VR::IS
+            | VR::LO
+            | VR::LT
+            | VR::PN
+            | VR::SH
+            | VR::ST
+            | VR::TM
+            | VR::UC
+            | VR::UI
+            | VR::UR
+            | VR::UT => {
+                let mut bytes = vec![0u8; len];
+                source.read_exact(&mut bytes).context(ReadValueSnafu)?;
+                let text = String::from_utf8_lossy(&bytes);
+                let text = text.trim_end_matches(['\0', ' ']);
+                let values: Vec<String> = text.split('\\').map(str::to_string).collect();
+                PrimitiveValue::Strs(values.into())
+            }
+            _ => {
+                let mut bytes = vec![0u8; len];
+                source.read_exact(&mut bytes).context(ReadValueSnafu)?;
+                PrimitiveValue::U8(bytes.into())
+            }
+        };
+        Ok(value)
+    }
 }
 
 impl<T: ?Sized> Decode for Box<T>
@@ -540,21 +862,21 @@ where
 {
     fn decode_header<S>(&self, source: &mut S) -> Result<(DataElementHeader, usize)>
     where
-        S: ?Sized + Read,
+        S: ?Sized + DicomRead,
     {
         (**self).decode_header(source)
     }
 
     fn decode_item_header<S>(&self, source: &mut S) -> Result<SequenceItemHeader>
     where
-        S: ?Sized + Read,
+        S: ?Sized + DicomRead,
     {
         (**self).decode_item_header(source)
     }
 
     fn decode_tag<S>(&self, source: &mut S) -> Result<Tag>
     where
-        S: ?Sized + Read,
+        S: ?Sized + DicomRead,
     {
         (**self).decode_tag(source)
     }
@@ -566,21 +888,21 @@ where
 {
     fn decode_header<S>(&self, source: &mut S) -> Result<(DataElementHeader, usize)>
     where
-        S: ?Sized + Read,
+        S: ?Sized + DicomRead,
     {
         (**self).decode_header(source)
     }
 
     fn decode_item_header<S>(&self, source: &mut S) -> Result<SequenceItemHeader>
     where
-        S: ?Sized + Read,
+        S: ?Sized + DicomRead,
     {
         (**self).decode_item_header(source)
     }
 
     fn decode_tag<S>(&self, source: &mut S) -> Result<Tag>
     where
-        S: ?Sized + Read,
+        S: ?Sized + DicomRead,
     {
         (**self).decode_tag(source)
     }
@@ -592,7 +914,7 @@ where
  * The specific behaviour of decoding, even when abstracted from the original source,
  * may depend on the transfer syntax.
  */
-pub trait DecodeFrom<S: ?Sized + Read> {
+pub trait DecodeFrom<S: ?Sized + DicomRead> {
     /** Fetch and decode the next data element header from the given source.
      * This method returns only the header of the element. At the end of this operation, the source
      * will be pointing at the element's value data, which should be read or skipped as necessary.
@@ -617,7 +939,7 @@ pub trait DecodeFrom<S: ?Sized + Read> {
 
 impl<S: ?Sized, T: ?Sized> DecodeFrom<S> for &T
 where
-    S: Read,
+    S: DicomRead,
     T: DecodeFrom<S>,
 {
     fn decode_header(&self, source: &mut S) -> Result<(DataElementHeader, usize)> {
@@ -635,7 +957,7 @@ where
 
 impl<S: ?Sized, T: ?Sized> DecodeFrom<S> for Box<T>
 where
-    S: Read,
+    S: DicomRead,
     T: DecodeFrom<S>,
 {
     fn decode_header(&self, source: &mut S) -> Result<(DataElementHeader, usize)> {
@@ -654,18 +976,232 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use dicom_core::header::Length;
 
-    fn is_decode_from<T: DecodeFrom<dyn Read>>(_decoder: &T) {}
+    fn is_decode_from<T: DecodeFrom<dyn DicomRead>>(_decoder: &T) {}
 
     #[allow(unused)]
     fn boxed_decoder_from_is_decoder_from<T>(decoder: T)
     where
-        T: DecodeFrom<dyn Read>,
+        T: DecodeFrom<dyn DicomRead>,
     {
         is_decode_from(&decoder);
         let boxed = Box::new(decoder);
         is_decode_from(&boxed);
-        let erased = boxed as Box<dyn DecodeFrom<dyn Read>>;
+        let erased = boxed as Box<dyn DecodeFrom<dyn DicomRead>>;
         is_decode_from(&erased);
     }
+
+    /// A minimal `BasicDecode` that only implements the single-value
+    /// methods, so the bulk `decode_us_into` default is what's under test.
+    struct MockBasicDecoder(Endianness);
+
+    impl BasicDecode for MockBasicDecoder {
+        fn endianness(&self) -> Endianness {
+            self.0
+        }
+
+        fn decode_us<S>(&self, mut source: S) -> std::io::Result<u16>
+        where
+            S: Read,
+        {
+            let mut buf = [0; 2];
+            source.read_exact(&mut buf)?;
+            Ok(match self.0 {
+                Endianness::Little => u16::from_le_bytes(buf),
+                Endianness::Big => u16::from_be_bytes(buf),
+            })
+        }
+
+        fn decode_ul<S>(&self, _source: S) -> std::io::Result<u32>
+        where
+            S: Read,
+        {
+            unimplemented!()
+        }
+
+        fn decode_uv<S>(&self, _source: S) -> std::io::Result<u64>
+        where
+            S: Read,
+        {
+            unimplemented!()
+        }
+
+        fn decode_ss<S>(&self, _source: S) -> std::io::Result<i16>
+        where
+            S: Read,
+        {
+            unimplemented!()
+        }
+
+        fn decode_sl<S>(&self, _source: S) -> std::io::Result<i32>
+        where
+            S: Read,
+        {
+            unimplemented!()
+        }
+
+        fn decode_sv<S>(&self, _source: S) -> std::io::Result<i64>
+        where
+            S: Read,
+        {
+            unimplemented!()
+        }
+
+        fn decode_fl<S>(&self, _source: S) -> std::io::Result<f32>
+        where
+            S: Read,
+        {
+            unimplemented!()
+        }
+
+        fn decode_fd<S>(&self, _source: S) -> std::io::Result<f64>
+        where
+            S: Read,
+        {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn decode_us_into_bulk_little_endian() {
+        let decoder = MockBasicDecoder(Endianness::Little);
+        let bytes = [0x01, 0x00, 0x02, 0x00, 0xff, 0xff];
+        let mut dst = [0u16; 3];
+        decoder.decode_us_into(&bytes[..], &mut dst).unwrap();
+        assert_eq!(dst, [1, 2, 0xffff]);
+    }
+
+    #[test]
+    fn decode_us_into_bulk_big_endian() {
+        let decoder = MockBasicDecoder(Endianness::Big);
+        let bytes = [0x00, 0x01, 0x00, 0x02, 0xff, 0xff];
+        let mut dst = [0u16; 3];
+        decoder.decode_us_into(&bytes[..], &mut dst).unwrap();
+        assert_eq!(dst, [1, 2, 0xffff]);
+    }
+
+    #[test]
+    fn decode_us_into_odd_trailing_byte_errors() {
+        let decoder = MockBasicDecoder(Endianness::Little);
+        // only 3 bytes available for 2 elements (4 bytes needed)
+        let bytes = [0x01, 0x00, 0x02];
+        let mut dst = [0u16; 2];
+        let err = decoder.decode_us_into(&bytes[..], &mut dst).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    }
+
+    impl Decode for MockBasicDecoder {
+        fn decode_header<S>(&self, _source: &mut S) -> Result<(DataElementHeader, usize)>
+        where
+            S: ?Sized + DicomRead,
+        {
+            unimplemented!()
+        }
+
+        fn decode_item_header<S>(&self, _source: &mut S) -> Result<SequenceItemHeader>
+        where
+            S: ?Sized + DicomRead,
+        {
+            unimplemented!()
+        }
+
+        fn decode_tag<S>(&self, _source: &mut S) -> Result<Tag>
+        where
+            S: ?Sized + DicomRead,
+        {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn decode_element_value_reads_aligned_numeric_values() {
+        let decoder = MockBasicDecoder(Endianness::Little);
+        let header = DataElementHeader::new(Tag(0x0028, 0x0010), VR::US, Length(4));
+        let mut source = TrackedReader::new(&[0x01, 0x00, 0x02, 0x00][..]);
+        let value = decoder
+            .decode_element_value(&header, &mut source, &DecodeOptions::new())
+            .unwrap();
+        match value {
+            PrimitiveValue::U16(v) => assert_eq!(&*v, &[1u16, 2u16]),
+            other => panic!("expected U16, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn decode_element_value_rejects_misaligned_numeric_length() {
+        // 3 bytes can't be evenly split into US (2-byte) elements; halving
+        // via plain integer division would silently under-read and desync
+        // the stream instead of erroring
+        let decoder = MockBasicDecoder(Endianness::Little);
+        let header = DataElementHeader::new(Tag(0x0028, 0x0010), VR::US, Length(3));
+        let mut source = TrackedReader::new(&[0x01, 0x00, 0x02][..]);
+        let err = decoder
+            .decode_element_value(&header, &mut source, &DecodeOptions::new())
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::MisalignedLength {
+                length: 3,
+                width: 2
+            }
+        ));
+    }
+
+    #[test]
+    fn decode_element_value_rejects_odd_length_unless_allowed() {
+        let decoder = MockBasicDecoder(Endianness::Little);
+        let header = DataElementHeader::new(Tag(0x0008, 0x0008), VR::CS, Length(3));
+        let mut source = TrackedReader::new(&b"ABC"[..]);
+        let options = DecodeOptions::new().with_allow_odd_length(false);
+        let err = decoder
+            .decode_element_value(&header, &mut source, &options)
+            .unwrap_err();
+        assert!(matches!(err, Error::OddLength { length: 3 }));
+    }
+
+    #[test]
+    fn decode_element_value_allows_odd_length_by_default() {
+        let decoder = MockBasicDecoder(Endianness::Little);
+        let header = DataElementHeader::new(Tag(0x0008, 0x0008), VR::CS, Length(3));
+        let mut source = TrackedReader::new(&b"ABC"[..]);
+        let value = decoder
+            .decode_element_value(&header, &mut source, &DecodeOptions::new())
+            .unwrap();
+        match value {
+            PrimitiveValue::Strs(v) => assert_eq!(&*v, &["ABC".to_string()]),
+            other => panic!("expected Strs, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn peek_tag_then_full_read_sees_the_same_bytes() {
+        let bytes = [0x01, 0x00, 0x02, 0x00];
+        let mut reader = TrackedReader::new(&bytes[..]);
+        let tag = reader.peek_tag(Endianness::Little).unwrap();
+        assert_eq!(tag, Tag(1, 2));
+        let mut buf = [0u8; 4];
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, bytes);
+    }
+
+    #[test]
+    fn peek_tag_after_partial_consumption_does_not_lose_bytes() {
+        // peek 4 bytes, consume only the first 2 via a direct read, then
+        // peek again: the still-buffered last 2 bytes must be included,
+        // not dropped in favor of 4 fresh bytes from the source
+        let bytes = [0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF, 0x11, 0x22];
+        let mut reader = TrackedReader::new(&bytes[..]);
+        reader.peek_tag(Endianness::Little).unwrap();
+        let mut buf = [0u8; 2];
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, [0xAA, 0xBB]);
+
+        let tag = reader.peek_tag(Endianness::Little).unwrap();
+        assert_eq!(tag, Tag(0xDDCC, 0xFFEE));
+
+        let mut rest = [0u8; 6];
+        reader.read_exact(&mut rest).unwrap();
+        assert_eq!(rest, [0xCC, 0xDD, 0xEE, 0xFF, 0x11, 0x22]);
+    }
 }
@@ -0,0 +1,102 @@
+//! Configuration of how strictly a decoder should enforce the standard
+//! when it encounters data that real-world DICOM often gets wrong:
+//! unrecognized explicit VRs, odd value lengths, mismatched reserved
+//! bytes, and implausible element lengths.
+
+/// What to do when an explicit VR decoder encounters a VR it doesn't
+/// recognize.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnknownVrBehavior {
+    /// Treat the element as if it had VR `UN`.
+    ConvertToUn,
+    /// Raise a decode error.
+    Error,
+}
+
+/// Options which control how lenient a [`Decode`](super::Decode) or
+/// [`DecodeFrom`](super::DecodeFrom) implementation is when reading data
+/// that deviates from the standard.
+///
+/// Built via the builder methods, starting from [`DecodeOptions::new`]:
+///
+/// ```
+/// # use dicom_encoding::decode::{DecodeOptions, UnknownVrBehavior};
+/// let options = DecodeOptions::new()
+///     .with_unknown_vr_behavior(UnknownVrBehavior::Error)
+///     .with_allow_odd_length(false);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeOptions {
+    unknown_vr_behavior: UnknownVrBehavior,
+    allow_odd_length: bool,
+    validate_reserved_bytes: bool,
+    max_element_length: u32,
+}
+
+impl Default for DecodeOptions {
+    fn default() -> Self {
+        DecodeOptions {
+            unknown_vr_behavior: UnknownVrBehavior::ConvertToUn,
+            allow_odd_length: true,
+            validate_reserved_bytes: false,
+            // generous but bounded: guards against a corrupt 0xFFFFFFF0
+            // length triggering a huge downstream allocation
+            max_element_length: 0x0010_0000 * 256, // 256 MiB
+        }
+    }
+}
+
+impl DecodeOptions {
+    /// Create a new set of decode options with the default, lenient
+    /// behavior.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set what happens when an unrecognized explicit VR is encountered.
+    pub fn with_unknown_vr_behavior(mut self, behavior: UnknownVrBehavior) -> Self {
+        self.unknown_vr_behavior = behavior;
+        self
+    }
+
+    /// Set whether an odd element value length is tolerated.
+    pub fn with_allow_odd_length(mut self, allow: bool) -> Self {
+        self.allow_odd_length = allow;
+        self
+    }
+
+    /// Set whether the reserved bytes of a long-form explicit VR header are
+    /// validated against the standard's fixed zero value.
+    pub fn with_validate_reserved_bytes(mut self, validate: bool) -> Self {
+        self.validate_reserved_bytes = validate;
+        self
+    }
+
+    /// Set the maximum element value length accepted before
+    /// [`Decode::decode_element_value`](super::Decode::decode_element_value)
+    /// rejects it with [`Error::ValueLengthExceedsLimit`](super::Error::ValueLengthExceedsLimit).
+    pub fn with_max_element_length(mut self, max: u32) -> Self {
+        self.max_element_length = max;
+        self
+    }
+
+    /// The configured behavior for unrecognized explicit VRs.
+    pub fn unknown_vr_behavior(&self) -> UnknownVrBehavior {
+        self.unknown_vr_behavior
+    }
+
+    /// Whether odd element value lengths are tolerated.
+    pub fn allow_odd_length(&self) -> bool {
+        self.allow_odd_length
+    }
+
+    /// Whether reserved bytes are validated.
+    pub fn validate_reserved_bytes(&self) -> bool {
+        self.validate_reserved_bytes
+    }
+
+    /// The maximum element value length accepted.
+    pub fn max_element_length(&self) -> u32 {
+        self.max_element_length
+    }
+}
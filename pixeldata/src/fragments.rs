@@ -0,0 +1,135 @@
+//! Grouping of encapsulated pixel data fragments into frames using the
+//! Basic Offset Table.
+
+/// Group raw encoded fragments into per-frame byte buffers.
+///
+/// When `offset_table` is non-empty, it is used to tell exactly which
+/// fragments (and how many of them) belong to each frame, since a single
+/// frame may span several fragments, as is common with large JPEG 2000 or
+/// JPEG-LS frames. When it is empty, the caller should fall back to the
+/// one-fragment-per-frame heuristic.
+pub(crate) fn group_fragments_by_frame<'a>(
+    fragments: &[&'a [u8]],
+    offset_table: &[u32],
+) -> Vec<Vec<u8>> {
+    debug_assert!(!offset_table.is_empty());
+
+    // byte offset of each fragment relative to the first one, so we can
+    // tell which offset-table boundary each fragment falls after
+    let mut fragment_offset = 0u32;
+    let fragment_offsets: Vec<u32> = fragments
+        .iter()
+        .map(|frag| {
+            let offset = fragment_offset;
+            fragment_offset += frag.len() as u32;
+            offset
+        })
+        .collect();
+
+    let mut frames = Vec::with_capacity(offset_table.len());
+    for (i, &start) in offset_table.iter().enumerate() {
+        let end = offset_table.get(i + 1).copied().unwrap_or(u32::MAX);
+        let mut frame = Vec::new();
+        for (frag, &offset) in fragments.iter().zip(&fragment_offsets) {
+            if offset >= start && offset < end {
+                frame.extend_from_slice(frag);
+            }
+        }
+        frames.push(frame);
+    }
+    frames
+}
+
+/// Collect only the fragment bytes belonging to a single frame, using the
+/// Basic Offset Table, without materializing any of the other frames.
+///
+/// This is the single-frame counterpart to [`group_fragments_by_frame`],
+/// meant for callers (such as per-frame decoding of large multi-frame
+/// series) that only need one frame's bytes and shouldn't pay to build and
+/// discard all the others. Returns `None` if `frame` is out of range of the
+/// offset table.
+pub(crate) fn fragment_range_for_frame(
+    fragments: &[&[u8]],
+    offset_table: &[u32],
+    frame: usize,
+) -> Option<Vec<u8>> {
+    let start = *offset_table.get(frame)?;
+    let end = offset_table.get(frame + 1).copied().unwrap_or(u32::MAX);
+
+    let mut out = Vec::new();
+    let mut fragment_offset = 0u32;
+    for frag in fragments {
+        let offset = fragment_offset;
+        fragment_offset += frag.len() as u32;
+        if offset >= end {
+            break;
+        }
+        if offset >= start {
+            out.extend_from_slice(frag);
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // three single-fragment frames, one fragment each
+    fn three_single_fragment_frames() -> (Vec<&'static [u8]>, Vec<u32>) {
+        let fragments: Vec<&[u8]> = vec![&[1, 1, 1], &[2, 2, 2, 2], &[3, 3]];
+        let offset_table = vec![0, 3, 7];
+        (fragments, offset_table)
+    }
+
+    #[test]
+    fn group_fragments_by_frame_first_frame() {
+        let (fragments, offset_table) = three_single_fragment_frames();
+        let frames = group_fragments_by_frame(&fragments, &offset_table);
+        assert_eq!(frames[0], vec![1, 1, 1]);
+    }
+
+    #[test]
+    fn group_fragments_by_frame_last_frame() {
+        let (fragments, offset_table) = three_single_fragment_frames();
+        let frames = group_fragments_by_frame(&fragments, &offset_table);
+        assert_eq!(frames[2], vec![3, 3]);
+    }
+
+    #[test]
+    fn group_fragments_by_frame_fragment_exactly_on_boundary() {
+        // the second fragment's offset (3) lands exactly on the second
+        // frame's start boundary, so it must belong to frame 1, not frame 0
+        let (fragments, offset_table) = three_single_fragment_frames();
+        let frames = group_fragments_by_frame(&fragments, &offset_table);
+        assert_eq!(frames[1], vec![2, 2, 2, 2]);
+    }
+
+    #[test]
+    fn group_fragments_by_frame_multi_fragment_frame() {
+        // frame 0 spans the first two fragments
+        let fragments: Vec<&[u8]> = vec![&[1, 1], &[1, 1], &[2, 2]];
+        let offset_table = vec![0, 4];
+        let frames = group_fragments_by_frame(&fragments, &offset_table);
+        assert_eq!(frames[0], vec![1, 1, 1, 1]);
+        assert_eq!(frames[1], vec![2, 2]);
+    }
+
+    #[test]
+    fn fragment_range_for_frame_matches_group_fragments_by_frame() {
+        let (fragments, offset_table) = three_single_fragment_frames();
+        let grouped = group_fragments_by_frame(&fragments, &offset_table);
+        for (i, expected) in grouped.iter().enumerate() {
+            assert_eq!(
+                fragment_range_for_frame(&fragments, &offset_table, i).as_ref(),
+                Some(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn fragment_range_for_frame_out_of_range_returns_none() {
+        let (fragments, offset_table) = three_single_fragment_frames();
+        assert_eq!(fragment_range_for_frame(&fragments, &offset_table, 3), None);
+    }
+}
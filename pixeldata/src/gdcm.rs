@@ -1,150 +1,376 @@
 //! Decode pixel data using GDCM when the default features are enabled.
 
 use crate::*;
-use dicom_encoding::adapters::DecodeError;
+use dicom_core::{DataElement, PrimitiveValue, VR};
+use dicom_encoding::adapters::{DecodeError, EncodeError};
 use dicom_encoding::transfer_syntax::TransferSyntaxIndex;
 use dicom_transfer_syntax_registry::TransferSyntaxRegistry;
 use gdcm_rs::{
-    decode_multi_frame_compressed, decode_single_frame_compressed, Error as GDCMError,
-    GDCMPhotometricInterpretation, GDCMTransferSyntax,
+    decode_multi_frame_compressed, decode_single_frame_compressed, encode_single_frame_compressed,
+    Error as GDCMError, GDCMPhotometricInterpretation, GDCMTransferSyntax,
 };
 use std::{convert::TryFrom, str::FromStr};
 
-impl<D> PixelDecoder for FileDicomObject<InMemDicomObject<D>>
+/// Attributes shared by whole-object and single-frame decoding, gathered
+/// in one place so the two entry points don't duplicate the Image Pixel
+/// module lookups.
+struct CommonPixelAttrs {
+    cols: u16,
+    rows: u16,
+    photometric_interpretation: PhotometricInterpretation,
+    pi_type: GDCMPhotometricInterpretation,
+    ts_type: GDCMTransferSyntax,
+    samples_per_pixel: u16,
+    bits_allocated: u16,
+    bits_stored: u16,
+    high_bit: u16,
+    pixel_representation: PixelRepresentation,
+    rescale_intercept: f64,
+    rescale_slope: f64,
+    number_of_frames: u32,
+    voi_lut_function: Option<VoiLutFunction>,
+    window: Option<WindowLevel>,
+}
+
+fn common_pixel_attrs<D>(obj: &FileDicomObject<InMemDicomObject<D>>) -> Result<CommonPixelAttrs>
 where
     D: DataDictionary + Clone,
 {
-    fn decode_pixel_data(&self) -> Result<DecodedPixelData> {
-        use super::attribute::*;
+    use super::attribute::*;
 
-        let pixel_data = pixel_data(self).context(GetAttributeSnafu)?;
-        let cols = cols(self).context(GetAttributeSnafu)?;
-        let rows = rows(self).context(GetAttributeSnafu)?;
-
-        let photometric_interpretation =
-            photometric_interpretation(self).context(GetAttributeSnafu)?;
-        let pi_type = GDCMPhotometricInterpretation::from_str(photometric_interpretation.as_str())
-            .map_err(|_| {
-                UnsupportedPhotometricInterpretationSnafu {
-                    pi: photometric_interpretation.clone(),
-                }
-                .build()
-            })?;
+    let cols = cols(obj).context(GetAttributeSnafu)?;
+    let rows = rows(obj).context(GetAttributeSnafu)?;
 
-        let transfer_syntax = &self.meta().transfer_syntax;
-        let registry =
-            TransferSyntaxRegistry
-                .get(&&transfer_syntax)
-                .context(UnknownTransferSyntaxSnafu {
-                    ts_uid: transfer_syntax,
-                })?;
-        let ts_type = GDCMTransferSyntax::from_str(&registry.uid()).map_err(|_| {
-            UnsupportedTransferSyntaxSnafu {
-                ts: transfer_syntax.clone(),
+    let photometric_interpretation = photometric_interpretation(obj).context(GetAttributeSnafu)?;
+    let pi_type = GDCMPhotometricInterpretation::from_str(photometric_interpretation.as_str())
+        .map_err(|_| {
+            UnsupportedPhotometricInterpretationSnafu {
+                pi: photometric_interpretation.clone(),
             }
             .build()
         })?;
 
-        let samples_per_pixel = samples_per_pixel(self).context(GetAttributeSnafu)?;
-        let bits_allocated = bits_allocated(self).context(GetAttributeSnafu)?;
-        let bits_stored = bits_stored(self).context(GetAttributeSnafu)?;
-        let high_bit = high_bit(self).context(GetAttributeSnafu)?;
-        let pixel_representation = pixel_representation(self).context(GetAttributeSnafu)?;
-        let rescale_intercept = rescale_intercept(self);
-        let rescale_slope = rescale_slope(self);
-        let number_of_frames = number_of_frames(self).context(GetAttributeSnafu)?;
-        let voi_lut_function = voi_lut_function(self).context(GetAttributeSnafu)?;
-        let voi_lut_function = voi_lut_function.and_then(|v| VoiLutFunction::try_from(&*v).ok());
+    let transfer_syntax = &obj.meta().transfer_syntax;
+    let registry =
+        TransferSyntaxRegistry
+            .get(&&transfer_syntax)
+            .context(UnknownTransferSyntaxSnafu {
+                ts_uid: transfer_syntax,
+            })?;
+    let ts_type = GDCMTransferSyntax::from_str(&registry.uid()).map_err(|_| {
+        UnsupportedTransferSyntaxSnafu {
+            ts: transfer_syntax.clone(),
+        }
+        .build()
+    })?;
+
+    let samples_per_pixel = samples_per_pixel(obj).context(GetAttributeSnafu)?;
+    let bits_allocated = bits_allocated(obj).context(GetAttributeSnafu)?;
+    let bits_stored = bits_stored(obj).context(GetAttributeSnafu)?;
+    let high_bit = high_bit(obj).context(GetAttributeSnafu)?;
+    let pixel_representation = pixel_representation(obj).context(GetAttributeSnafu)?;
+    let rescale_intercept = rescale_intercept(obj);
+    let rescale_slope = rescale_slope(obj);
+    let number_of_frames = number_of_frames(obj).context(GetAttributeSnafu)?;
+    let voi_lut_function = voi_lut_function(obj).context(GetAttributeSnafu)?;
+    let voi_lut_function = voi_lut_function.and_then(|v| VoiLutFunction::try_from(&*v).ok());
+
+    let window = if let Some(window_center) = window_center(obj).context(GetAttributeSnafu)? {
+        let window_width = window_width(obj).context(GetAttributeSnafu)?;
+        window_width.map(|width| WindowLevel {
+            center: window_center,
+            width,
+        })
+    } else {
+        None
+    };
+
+    Ok(CommonPixelAttrs {
+        cols,
+        rows,
+        photometric_interpretation,
+        pi_type,
+        ts_type,
+        samples_per_pixel,
+        bits_allocated,
+        bits_stored,
+        high_bit,
+        pixel_representation,
+        rescale_intercept,
+        rescale_slope,
+        number_of_frames,
+        voi_lut_function,
+        window,
+    })
+}
+
+impl<D> PixelDecoder for FileDicomObject<InMemDicomObject<D>>
+where
+    D: DataDictionary + Clone,
+{
+    fn decode_pixel_data(&self) -> Result<DecodedPixelData> {
+        let attrs = common_pixel_attrs(self)?;
+
+        let mut data = Vec::new();
+        let mut photometric_interpretation = attrs.photometric_interpretation;
+        let mut samples_per_pixel = attrs.samples_per_pixel;
+        for frame in 0..attrs.number_of_frames {
+            let frame_data = self.decode_pixel_data_frame(frame)?;
+            // each frame is already expanded (e.g. PALETTE COLOR -> RGB), so
+            // reuse its derived fields instead of recomputing from the
+            // pre-expansion attrs and risking the two falling out of sync
+            photometric_interpretation = frame_data.photometric_interpretation;
+            samples_per_pixel = frame_data.samples_per_pixel;
+            data.extend_from_slice(&frame_data.data);
+        }
+
+        Ok(DecodedPixelData {
+            data: Cow::from(data),
+            cols: attrs.cols.into(),
+            rows: attrs.rows.into(),
+            number_of_frames: attrs.number_of_frames,
+            photometric_interpretation,
+            samples_per_pixel,
+            planar_configuration: PlanarConfiguration::Standard,
+            bits_allocated: attrs.bits_allocated,
+            bits_stored: attrs.bits_stored,
+            high_bit: attrs.high_bit,
+            pixel_representation: attrs.pixel_representation,
+            rescale_intercept: attrs.rescale_intercept,
+            rescale_slope: attrs.rescale_slope,
+            voi_lut_function: attrs.voi_lut_function,
+            window: attrs.window,
+        })
+    }
+
+    /// Decode exactly one frame of pixel data, without materializing any of
+    /// the other frames. This bounds peak memory on large multi-frame
+    /// series, where decoding the whole object at once is prohibitive.
+    fn decode_pixel_data_frame(&self, frame: u32) -> Result<DecodedPixelData> {
+        use super::attribute::*;
+
+        let pixel_data = pixel_data(self).context(GetAttributeSnafu)?;
+        let attrs = common_pixel_attrs(self)?;
+
+        let gdcm_error_mapper = |source: GDCMError| DecodeError::Custom {
+            message: source.to_string(),
+            source: Some(Box::new(source)),
+        };
 
         let decoded_pixel_data = match pixel_data.value() {
             Value::PixelSequence(v) => {
                 let fragments = v.fragments();
-                let gdcm_error_mapper = |source: GDCMError| DecodeError::Custom {
-                    message: source.to_string(),
-                    source: Some(Box::new(source)),
-                };
-                if fragments.len() > 1 {
-                    // Bundle fragments and decode multi-frame dicoms
-                    let dims = [cols.into(), rows.into(), number_of_frames.into()];
-                    let fragments: Vec<_> = fragments.iter().map(|frag| frag.as_slice()).collect();
-                    decode_multi_frame_compressed(
-                        fragments.as_slice(),
-                        &dims,
-                        pi_type,
-                        ts_type,
-                        samples_per_pixel,
-                        bits_allocated,
-                        bits_stored,
-                        high_bit,
-                        pixel_representation as u16,
+                let offset_table = v.offset_table();
+                let raw_fragments: Vec<_> = fragments.iter().map(|frag| frag.as_slice()).collect();
+
+                let frame_bytes: Vec<u8> = if !offset_table.is_empty() {
+                    // select just the fragment(s) that make up this frame,
+                    // without materializing (and discarding) every other
+                    // frame first
+                    crate::fragments::fragment_range_for_frame(
+                        &raw_fragments,
+                        offset_table,
+                        frame as usize,
                     )
-                    .map_err(gdcm_error_mapper)
-                    .context(DecodePixelDataSnafu)?
-                    .to_vec()
+                    .context(InvalidPixelDataSnafu)?
+                } else if raw_fragments.len() as u32 == attrs.number_of_frames {
+                    // one fragment per frame
+                    raw_fragments
+                        .get(frame as usize)
+                        .map(|f| f.to_vec())
+                        .context(InvalidPixelDataSnafu)?
                 } else {
-                    decode_single_frame_compressed(
-                        &fragments[0],
-                        cols.into(),
-                        rows.into(),
-                        pi_type,
-                        ts_type,
-                        samples_per_pixel,
-                        bits_allocated,
-                        bits_stored,
-                        high_bit,
-                        pixel_representation as u16,
-                    )
-                    .map_err(gdcm_error_mapper)
-                    .context(DecodePixelDataSnafu)?
-                    .to_vec()
-                }
+                    // no Basic Offset Table and an ambiguous fragment count:
+                    // there is no reliable way to isolate a single frame, so
+                    // concatenate everything and let the codec sort it out.
+                    raw_fragments.concat()
+                };
+
+                decode_single_frame_compressed(
+                    &frame_bytes,
+                    attrs.cols.into(),
+                    attrs.rows.into(),
+                    attrs.pi_type,
+                    attrs.ts_type,
+                    attrs.samples_per_pixel,
+                    attrs.bits_allocated,
+                    attrs.bits_stored,
+                    attrs.high_bit,
+                    attrs.pixel_representation as u16,
+                )
+                .map_err(gdcm_error_mapper)
+                .context(DecodePixelDataSnafu)?
+                .to_vec()
             }
             Value::Primitive(p) => {
-                // Non-encoded, just return the pixel data of the first frame
-                p.to_bytes().to_vec()
+                if frame >= attrs.number_of_frames {
+                    return InvalidPixelDataSnafu.fail();
+                }
+                let bytes = p.to_bytes();
+                let frame_size = bytes.len() / attrs.number_of_frames.max(1) as usize;
+                let start = frame as usize * frame_size;
+                bytes
+                    .get(start..start + frame_size)
+                    .context(InvalidPixelDataSnafu)?
+                    .to_vec()
             }
             Value::Sequence(_) => InvalidPixelDataSnafu.fail()?,
         };
 
+        // `PALETTE COLOR` pixel data holds single-sample indices that must
+        // be expanded into RGB through the Palette Color LUTs before the
+        // data is usable.
+        let (decoded_pixel_data, samples_per_pixel) =
+            if attrs.photometric_interpretation == PhotometricInterpretation::PaletteColor {
+                let indices: Vec<u16> = if attrs.bits_allocated == 16 {
+                    decoded_pixel_data
+                        .chunks_exact(2)
+                        .map(|c| u16::from_ne_bytes([c[0], c[1]]))
+                        .collect()
+                } else {
+                    decoded_pixel_data.iter().map(|&b| b as u16).collect()
+                };
+                (crate::lut::apply_palette_color_lut(self, &indices)?, 3)
+            } else {
+                (decoded_pixel_data, attrs.samples_per_pixel)
+            };
+
         // pixels are already interpreted,
         // set new photometric interpretation
         let new_pi = match samples_per_pixel {
             1 => PhotometricInterpretation::Monochrome2,
             3 => PhotometricInterpretation::Rgb,
-            _ => photometric_interpretation,
-        };
-
-        let window = if let Some(window_center) = window_center(self).context(GetAttributeSnafu)? {
-            let window_width = window_width(self).context(GetAttributeSnafu)?;
-
-            window_width.map(|width| WindowLevel {
-                center: window_center,
-                width,
-            })
-        } else {
-            None
+            _ => attrs.photometric_interpretation,
         };
 
         Ok(DecodedPixelData {
             data: Cow::from(decoded_pixel_data),
-            cols: cols.into(),
-            rows: rows.into(),
-            number_of_frames,
+            cols: attrs.cols.into(),
+            rows: attrs.rows.into(),
+            number_of_frames: 1,
             photometric_interpretation: new_pi,
             samples_per_pixel,
             planar_configuration: PlanarConfiguration::Standard,
-            bits_allocated,
-            bits_stored,
-            high_bit,
-            pixel_representation,
-            rescale_intercept,
-            rescale_slope,
-            voi_lut_function,
-            window,
+            bits_allocated: attrs.bits_allocated,
+            bits_stored: attrs.bits_stored,
+            high_bit: attrs.high_bit,
+            pixel_representation: attrs.pixel_representation,
+            rescale_intercept: attrs.rescale_intercept,
+            rescale_slope: attrs.rescale_slope,
+            voi_lut_function: attrs.voi_lut_function,
+            window: attrs.window,
         })
     }
 }
 
+/// Re-encodes the pixel data of a DICOM object into a different transfer
+/// syntax, rewriting the Image Pixel attributes that must stay consistent
+/// with the new encoding.
+///
+/// Unlike [`PixelDecoder`], which only goes from encoded bytes to a decoded
+/// in-memory representation, this trait goes the other way: it takes the
+/// object's current (decoded or differently-encoded) pixel data and produces
+/// a new encoding understood by GDCM, such as JPEG 2000 Lossless, JPEG-LS,
+/// or RLE Lossless.
+pub trait EncodePixelData {
+    /// Re-encode the object's pixel data using the given transfer syntax,
+    /// replacing the Pixel Data element and the attributes that describe it
+    /// in place.
+    fn transcode(&mut self, dest_ts: &str) -> Result<()>;
+}
+
+impl<D> EncodePixelData for FileDicomObject<InMemDicomObject<D>>
+where
+    D: DataDictionary + Clone,
+{
+    fn transcode(&mut self, dest_ts: &str) -> Result<()> {
+        use super::attribute::*;
+
+        let registry = TransferSyntaxRegistry
+            .get(dest_ts)
+            .context(UnknownTransferSyntaxSnafu { ts_uid: dest_ts })?;
+        let dest_ts_type = GDCMTransferSyntax::from_str(&registry.uid()).map_err(|_| {
+            UnsupportedTransferSyntaxSnafu {
+                ts: dest_ts.to_string(),
+            }
+            .build()
+        })?;
+
+        // decode first: this normalizes whatever the current encoding is
+        // (native or encapsulated) into a single contiguous native buffer.
+        let decoded = self.decode_pixel_data()?;
+
+        let cols = decoded.cols;
+        let rows = decoded.rows;
+        let samples_per_pixel = decoded.samples_per_pixel;
+        let bits_allocated = decoded.bits_allocated;
+        let bits_stored = decoded.bits_stored;
+        let high_bit = decoded.high_bit;
+        let pixel_representation = decoded.pixel_representation;
+        let pi_type = GDCMPhotometricInterpretation::from_str(
+            decoded.photometric_interpretation.as_str(),
+        )
+        .map_err(|_| {
+            UnsupportedPhotometricInterpretationSnafu {
+                pi: decoded.photometric_interpretation.to_string(),
+            }
+            .build()
+        })?;
+
+        let gdcm_error_mapper = |source: GDCMError| EncodeError::Custom {
+            message: source.to_string(),
+            source: Some(Box::new(source)),
+        };
+
+        let mut fragments = Vec::with_capacity(decoded.number_of_frames as usize);
+        for frame in 0..decoded.number_of_frames {
+            let frame_data = decoded.frame_data(frame).context(GetAttributeSnafu)?;
+            let encoded = encode_single_frame_compressed(
+                frame_data,
+                cols,
+                rows,
+                pi_type,
+                dest_ts_type,
+                samples_per_pixel,
+                bits_allocated,
+                bits_stored,
+                high_bit,
+                pixel_representation as u16,
+            )
+            .map_err(gdcm_error_mapper)
+            .context(EncodePixelDataSnafu)?;
+            fragments.push(encoded.to_vec());
+        }
+
+        self.put(DataElement::new(
+            tags::PIXEL_DATA,
+            VR::OB,
+            Value::PixelSequence(PixelFragmentSequence::new(vec![], fragments)),
+        ));
+
+        // keep the Image Pixel module consistent with the new encoding
+        self.put(DataElement::new(
+            tags::BITS_ALLOCATED,
+            VR::US,
+            PrimitiveValue::from(bits_allocated),
+        ));
+        self.put(DataElement::new(
+            tags::PHOTOMETRIC_INTERPRETATION,
+            VR::CS,
+            PrimitiveValue::from(decoded.photometric_interpretation.to_string()),
+        ));
+        self.put(DataElement::new(
+            tags::PLANAR_CONFIGURATION,
+            VR::US,
+            PrimitiveValue::from(decoded.planar_configuration as u16),
+        ));
+
+        self.meta_mut().transfer_syntax = registry.uid().to_string();
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[cfg(any(feature = "ndarray", feature = "image"))]
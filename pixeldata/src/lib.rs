@@ -0,0 +1,8 @@
+#[cfg(feature = "gdcm")]
+mod gdcm;
+#[cfg(not(feature = "gdcm"))]
+mod rle;
+
+mod fragments;
+mod lut;
+mod transform;
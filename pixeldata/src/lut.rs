@@ -0,0 +1,195 @@
+//! Application of the Palette Color Lookup Table to single-sample
+//! `PALETTE COLOR` pixel data, expanding it into interleaved RGB.
+
+use crate::attribute::GetAttributeSnafu;
+use crate::{FileDicomObject, InMemDicomObject, Result};
+use dicom_core::dictionary::DataDictionary;
+use dicom_core::Tag;
+use snafu::OptionExt;
+
+/// One channel (red, green or blue) of a Palette Color Lookup Table.
+#[derive(Debug, Clone)]
+struct PaletteLut {
+    first_input_value: u32,
+    entries: Vec<u16>,
+}
+
+impl PaletteLut {
+    /// Map a raw pixel index through this channel's LUT, clamping to the
+    /// valid entry range as required by the standard.
+    fn lookup(&self, index: u32) -> u16 {
+        if self.entries.is_empty() {
+            return 0;
+        }
+        let offset = index.saturating_sub(self.first_input_value) as usize;
+        let offset = offset.min(self.entries.len() - 1);
+        self.entries[offset]
+    }
+}
+
+/// Descriptor and data tags for one Palette Color LUT channel.
+struct PaletteLutTags {
+    descriptor: Tag,
+    data: Tag,
+}
+
+const RED_LUT: PaletteLutTags = PaletteLutTags {
+    descriptor: Tag(0x0028, 0x1101),
+    data: Tag(0x0028, 0x1201),
+};
+const GREEN_LUT: PaletteLutTags = PaletteLutTags {
+    descriptor: Tag(0x0028, 0x1102),
+    data: Tag(0x0028, 0x1202),
+};
+const BLUE_LUT: PaletteLutTags = PaletteLutTags {
+    descriptor: Tag(0x0028, 0x1103),
+    data: Tag(0x0028, 0x1203),
+};
+
+fn read_palette_lut<D>(
+    obj: &FileDicomObject<InMemDicomObject<D>>,
+    tags: &PaletteLutTags,
+) -> Result<PaletteLut>
+where
+    D: DataDictionary + Clone,
+{
+    let descriptor = obj
+        .element(tags.descriptor)
+        .ok()
+        .context(GetAttributeSnafu)?;
+    let descriptor = descriptor.to_multi_int::<i32>().context(GetAttributeSnafu)?;
+    let number_of_entries = match descriptor[0] {
+        0 => 65536u32,
+        n => n as u32,
+    };
+    let first_input_value = descriptor[1] as u32;
+    let bits_per_entry = descriptor[2];
+
+    let data = obj.element(tags.data).ok().context(GetAttributeSnafu)?;
+    let words = data.to_multi_int::<u16>().context(GetAttributeSnafu)?;
+
+    let mut entries = unpack_lut_entries(&words, bits_per_entry, number_of_entries);
+    if bits_per_entry == 16 {
+        // keep only the high-order byte, so every LUT yields 8-bit values
+        for entry in &mut entries {
+            *entry >>= 8;
+        }
+    }
+
+    Ok(PaletteLut {
+        first_input_value,
+        entries,
+    })
+}
+
+/// Unpack a LUT data element's 16-bit words into one value per entry: taken
+/// directly when `bits_per_entry` is 16, or split into two 8-bit entries per
+/// word (low byte first) otherwise.
+fn unpack_lut_entries(words: &[u16], bits_per_entry: i32, number_of_entries: u32) -> Vec<u16> {
+    if bits_per_entry == 16 {
+        words.iter().copied().take(number_of_entries as usize).collect()
+    } else {
+        words
+            .iter()
+            .flat_map(|&word| [(word & 0x00ff) as u16, (word >> 8) as u16])
+            .take(number_of_entries as usize)
+            .collect()
+    }
+}
+
+/// Expand single-sample `PALETTE COLOR` indices into interleaved RGB bytes
+/// by applying the Red/Green/Blue Palette Color Lookup Tables found in
+/// `obj`. `indices` holds one index per pixel, already native-endian.
+pub(crate) fn apply_palette_color_lut<D>(
+    obj: &FileDicomObject<InMemDicomObject<D>>,
+    indices: &[u16],
+) -> Result<Vec<u8>>
+where
+    D: DataDictionary + Clone,
+{
+    let red = read_palette_lut(obj, &RED_LUT)?;
+    let green = read_palette_lut(obj, &GREEN_LUT)?;
+    let blue = read_palette_lut(obj, &BLUE_LUT)?;
+
+    // LUT entries may be 8- or 16-bit; the output is always rendered as
+    // 8 bits per sample, taking the high-order byte of wider entries
+    // (already done by `read_palette_lut`).
+    let mut out = Vec::with_capacity(indices.len() * 3);
+    for &index in indices {
+        let index = index as u32;
+        out.push(red.lookup(index) as u8);
+        out.push(green.lookup(index) as u8);
+        out.push(blue.lookup(index) as u8);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unpack_lut_entries_splits_8_bit_packed_words() {
+        // low byte first within each word
+        let words = [0x2010, 0x4030];
+        assert_eq!(
+            unpack_lut_entries(&words, 8, 4),
+            vec![0x10, 0x20, 0x30, 0x40]
+        );
+    }
+
+    #[test]
+    fn unpack_lut_entries_truncates_to_number_of_entries() {
+        let words = [0x2010, 0x4030];
+        assert_eq!(unpack_lut_entries(&words, 8, 3), vec![0x10, 0x20, 0x30]);
+    }
+
+    #[test]
+    fn unpack_lut_entries_takes_16_bit_words_directly() {
+        let words = [0x1234, 0x5678];
+        assert_eq!(unpack_lut_entries(&words, 16, 2), vec![0x1234, 0x5678]);
+    }
+
+    #[test]
+    fn palette_lut_lookup_reads_unpacked_entries() {
+        let lut = PaletteLut {
+            first_input_value: 0,
+            entries: vec![0x10, 0x20, 0x30, 0x40],
+        };
+        assert_eq!(lut.lookup(0), 0x10);
+        assert_eq!(lut.lookup(1), 0x20);
+        assert_eq!(lut.lookup(2), 0x30);
+        assert_eq!(lut.lookup(3), 0x40);
+    }
+
+    #[test]
+    fn palette_lut_lookup_honors_first_input_value() {
+        let lut = PaletteLut {
+            first_input_value: 10,
+            entries: vec![1, 2, 3],
+        };
+        assert_eq!(lut.lookup(10), 1);
+        assert_eq!(lut.lookup(12), 3);
+    }
+
+    #[test]
+    fn palette_lut_lookup_clamps_out_of_range_index() {
+        let lut = PaletteLut {
+            first_input_value: 10,
+            entries: vec![5, 6, 7],
+        };
+        // below the table: clamped by the saturating_sub, lands on entry 0
+        assert_eq!(lut.lookup(0), 5);
+        // past the end of the table: clamped to the last entry
+        assert_eq!(lut.lookup(100), 7);
+    }
+
+    #[test]
+    fn palette_lut_lookup_empty_entries_returns_zero() {
+        let lut = PaletteLut {
+            first_input_value: 0,
+            entries: vec![],
+        };
+        assert_eq!(lut.lookup(0), 0);
+    }
+}
@@ -0,0 +1,107 @@
+//! Vectorized application of the Modality LUT (rescale slope/intercept) and
+//! VOI LUT/windowing transforms used by [`super::ConvertOptions`] and the
+//! `to_ndarray_with_options` conversion path.
+//!
+//! Each transform is compiled into multiple CPU-feature variants via
+//! function multiversioning, with runtime dispatch picking the best one
+//! available and a scalar fallback for everything else.
+
+use multiversion::multiversion;
+
+/// Apply the Modality LUT's linear rescale, `y = x * slope + intercept`, to
+/// every sample in place.
+#[multiversion(targets("x86_64+avx2", "x86_64+sse4.1", "aarch64+neon"))]
+pub(crate) fn apply_rescale(samples: &mut [f64], slope: f64, intercept: f64) {
+    for sample in samples.iter_mut() {
+        *sample = *sample * slope + intercept;
+    }
+}
+
+/// Apply a VOI window to every sample in place, mapping intensities onto
+/// `0.0..=1.0` using the linear windowing function of DICOM PS3.3
+/// C.11.2.1.2.1.
+#[multiversion(targets("x86_64+avx2", "x86_64+sse4.1", "aarch64+neon"))]
+pub(crate) fn apply_window(samples: &mut [f64], center: f64, width: f64) {
+    let width = width.max(1.0);
+    let low = center - 0.5 - (width - 1.0) / 2.0;
+    let high = center - 0.5 + (width - 1.0) / 2.0;
+    for sample in samples.iter_mut() {
+        *sample = if *sample <= low {
+            0.0
+        } else if *sample >= high {
+            1.0
+        } else {
+            (*sample - (center - 0.5)) / (width - 1.0) + 0.5
+        };
+    }
+}
+
+/// Apply the Modality LUT rescale followed by a VOI window to every sample
+/// in place, in the order `to_ndarray_with_options` is expected to need
+/// them: rescale raw stored values first, then window the result.
+pub(crate) fn apply_modality_and_voi_lut(
+    samples: &mut [f64],
+    rescale: Option<(f64, f64)>,
+    window: Option<(f64, f64)>,
+) {
+    if let Some((slope, intercept)) = rescale {
+        apply_rescale(samples, slope, intercept);
+    }
+    if let Some((center, width)) = window {
+        apply_window(samples, center, width);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_rescale_applies_slope_and_intercept() {
+        let mut samples = [0.0, 1.0, 2.0];
+        apply_rescale(&mut samples, 2.0, 1.0);
+        assert_eq!(samples, [1.0, 3.0, 5.0]);
+    }
+
+    #[test]
+    fn apply_window_width_one_is_a_step_function_at_center() {
+        // with width == 1.0, low == high == center - 0.5 == 9.5: the
+        // window collapses to a hard step rather than a linear ramp, and
+        // the <= low branch is checked first, so exactly 9.5 maps to 0.0
+        let mut samples = [0.0, 9.0, 10.0, 11.0];
+        apply_window(&mut samples, 10.0, 1.0);
+        assert_eq!(samples, [0.0, 0.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn apply_window_clamps_below_and_above_range() {
+        let mut samples = [-100.0, 100.0];
+        apply_window(&mut samples, 0.0, 10.0);
+        assert_eq!(samples, [0.0, 1.0]);
+    }
+
+    #[test]
+    fn apply_window_maps_ramp_midpoint_to_half() {
+        // the linear ramp's exact midpoint is center - 0.5, per the
+        // DICOM PS3.3 C.11.2.1.2.1 windowing function
+        let mut samples = [49.5];
+        apply_window(&mut samples, 50.0, 100.0);
+        assert_eq!(samples, [0.5]);
+    }
+
+    #[test]
+    fn apply_modality_and_voi_lut_applies_both_in_order() {
+        let mut samples = [0.0];
+        apply_modality_and_voi_lut(&mut samples, Some((2.0, 0.0)), Some((0.5, 4.0)));
+        // rescale: 0.0 * 2.0 + 0.0 = 0.0, which lands exactly on the
+        // window's midpoint (center - 0.5 == 0.0)
+        assert_eq!(samples, [0.5]);
+    }
+
+    #[test]
+    fn apply_modality_and_voi_lut_skips_missing_steps() {
+        let mut samples = [3.0];
+        apply_modality_and_voi_lut(&mut samples, None, None);
+        assert_eq!(samples, [3.0]);
+    }
+}